@@ -0,0 +1,56 @@
+// Copyright © 2019 Intel Corporation
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+//! An [`AmlSink`] adapter over [`bytes::BufMut`], gated behind the `bytes`
+//! feature so the core crate stays `no_std` + `alloc`-only.
+//!
+//! This lets callers stream a table straight into a `BytesMut` (or any other
+//! `BufMut` implementation) without first materializing it as a `Vec<u8>`
+//! inside an [`Sdt`](crate::sdt::Sdt).
+
+use crate::AmlSink;
+use bytes::BufMut;
+
+/// Streams [`Aml::to_aml_bytes`](crate::Aml::to_aml_bytes) output directly
+/// into a `bytes::BufMut`, e.g. a `BytesMut` staging a guest-memory region.
+pub struct BufMutSink<'a, B: BufMut> {
+    buf: &'a mut B,
+}
+
+impl<'a, B: BufMut> BufMutSink<'a, B> {
+    pub fn new(buf: &'a mut B) -> Self {
+        BufMutSink { buf }
+    }
+}
+
+impl<'a, B: BufMut> AmlSink for BufMutSink<'a, B> {
+    fn byte(&mut self, byte: u8) {
+        self.buf.put_u8(byte);
+    }
+
+    fn vec(&mut self, data: &[u8]) {
+        self.buf.put_slice(data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BufMutSink;
+    use crate::sdt::Sdt;
+    use crate::Aml;
+    use bytes::BytesMut;
+
+    #[test]
+    fn test_buf_mut_sink_round_trips_sdt() {
+        let mut sdt = Sdt::new(*b"TEST", 40, 1, *b"CLOUDH", *b"TESTTEST", 1);
+        sdt.write_u32(36, 0x12345678);
+
+        let mut buf = BytesMut::with_capacity(sdt.len());
+        let mut sink = BufMutSink::new(&mut buf);
+        sdt.to_aml_bytes(&mut sink);
+
+        assert_eq!(buf.as_ref(), sdt.as_slice());
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright © 2019 Intel Corporation
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+//! Typed builder for the Fixed ACPI Description Table (FADT), covering the
+//! `GenericAddress`-based register blocks and the DSDT pointer slot that
+//! every VMM needs to fill in.
+
+use crate::sdt::{GenericAddress, Sdt};
+use crate::{Aml, AmlSink};
+
+const FADT_REVISION: u8 = 6;
+// ACPI 6.x FADT length, fixed regardless of how many of the register blocks
+// below a given platform actually uses.
+const FADT_LENGTH: u32 = 276;
+
+const DSDT: usize = 40;
+const SCI_INT: usize = 46;
+const SMI_CMD: usize = 48;
+const PM1A_EVT_BLK: usize = 56;
+const PM1A_CNT_BLK: usize = 64;
+const PM_TMR_BLK: usize = 76;
+const GPE0_BLK: usize = 80;
+const FLAGS: usize = 112;
+const RESET_REG: usize = 116;
+const RESET_VALUE: usize = 128;
+const X_DSDT: usize = 140;
+const X_PM1A_EVT_BLK: usize = 148;
+const X_PM1A_CNT_BLK: usize = 172;
+const X_PM_TMR_BLK: usize = 208;
+const X_GPE0_BLK: usize = 220;
+const SLEEP_CONTROL_REG: usize = 244;
+const SLEEP_STATUS_REG: usize = 256;
+
+/// Builder for the FADT. Each `set_*` method patches the relevant fixed
+/// offset and re-runs the table's checksum.
+pub struct Fadt {
+    sdt: Sdt,
+}
+
+impl Fadt {
+    pub fn new(oem_id: [u8; 6], oem_table: [u8; 8], oem_revision: u32) -> Self {
+        let sdt = Sdt::new(
+            *b"FACP",
+            FADT_LENGTH,
+            FADT_REVISION,
+            oem_id,
+            oem_table,
+            oem_revision,
+        );
+        Fadt { sdt }
+    }
+
+    /// Records the DSDT's 32-bit location. Callers targeting a guest
+    /// physical address above 4G should also call [`Fadt::set_x_dsdt`].
+    pub fn set_dsdt(&mut self, address: u32) -> &mut Self {
+        self.sdt.write_u32(DSDT, address);
+        self
+    }
+
+    pub fn set_x_dsdt(&mut self, address: u64) -> &mut Self {
+        self.sdt.write_u64(X_DSDT, address);
+        self
+    }
+
+    pub fn set_sci_interrupt(&mut self, sci_int: u16) -> &mut Self {
+        self.sdt.write_u16(SCI_INT, sci_int);
+        self
+    }
+
+    pub fn set_smi_command(&mut self, smi_cmd: u32) -> &mut Self {
+        self.sdt.write_u32(SMI_CMD, smi_cmd);
+        self
+    }
+
+    pub fn set_flags(&mut self, flags: u32) -> &mut Self {
+        self.sdt.write_u32(FLAGS, flags);
+        self
+    }
+
+    pub fn set_pm1a_event_block(&mut self, block: GenericAddress) -> &mut Self {
+        self.sdt.write_u32(PM1A_EVT_BLK, block.address as u32);
+        self.sdt.write_bytes(X_PM1A_EVT_BLK, &block.to_bytes());
+        self
+    }
+
+    pub fn set_pm1a_control_block(&mut self, block: GenericAddress) -> &mut Self {
+        self.sdt.write_u32(PM1A_CNT_BLK, block.address as u32);
+        self.sdt.write_bytes(X_PM1A_CNT_BLK, &block.to_bytes());
+        self
+    }
+
+    pub fn set_pm_timer_block(&mut self, block: GenericAddress) -> &mut Self {
+        self.sdt.write_u32(PM_TMR_BLK, block.address as u32);
+        self.sdt.write_bytes(X_PM_TMR_BLK, &block.to_bytes());
+        self
+    }
+
+    pub fn set_gpe0_block(&mut self, block: GenericAddress) -> &mut Self {
+        self.sdt.write_u32(GPE0_BLK, block.address as u32);
+        self.sdt.write_bytes(X_GPE0_BLK, &block.to_bytes());
+        self
+    }
+
+    pub fn set_reset_register(&mut self, reset_reg: GenericAddress, reset_value: u8) -> &mut Self {
+        self.sdt.write_bytes(RESET_REG, &reset_reg.to_bytes());
+        self.sdt.write_u8(RESET_VALUE, reset_value);
+        self
+    }
+
+    pub fn set_sleep_control_register(&mut self, block: GenericAddress) -> &mut Self {
+        self.sdt.write_bytes(SLEEP_CONTROL_REG, &block.to_bytes());
+        self
+    }
+
+    pub fn set_sleep_status_register(&mut self, block: GenericAddress) -> &mut Self {
+        self.sdt.write_bytes(SLEEP_STATUS_REG, &block.to_bytes());
+        self
+    }
+}
+
+impl Aml for Fadt {
+    fn to_aml_bytes(&self, sink: &mut dyn AmlSink) {
+        self.sdt.to_aml_bytes(sink);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fadt;
+    use crate::sdt::GenericAddress;
+
+    #[test]
+    fn test_fadt_checksum_and_length() {
+        let mut fadt = Fadt::new(*b"CLOUDH", *b"TESTTEST", 1);
+        fadt.set_x_dsdt(0x1000_0000);
+        fadt.set_sci_interrupt(9);
+        fadt.set_pm1a_event_block(GenericAddress::io_port_address::<u32>(0x600));
+        fadt.set_reset_register(GenericAddress::io_port_address::<u8>(0xcf9), 6);
+
+        assert_eq!(fadt.sdt.len(), 276);
+        let bytes = fadt.sdt.as_slice();
+        assert_eq!(bytes.len(), 276);
+
+        let sum = bytes.iter().fold(0u8, |acc, byte| acc.wrapping_add(*byte));
+        assert_eq!(sum, 0);
+    }
+}
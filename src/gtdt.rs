@@ -0,0 +1,105 @@
+// Copyright © 2019 Intel Corporation
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+//! Typed builder for the Generic Timer Description Table (GTDT), describing
+//! the ARM generic timer interrupts available to the guest.
+
+use crate::sdt::Sdt;
+use crate::{Aml, AmlSink};
+
+const GTDT_REVISION: u8 = 3;
+const GTDT_LENGTH: u32 = 96;
+
+const CNT_CONTROL_BASE: usize = 36;
+const SECURE_EL1_TIMER_GSIV: usize = 48;
+const SECURE_EL1_TIMER_FLAGS: usize = 52;
+const NON_SECURE_EL1_TIMER_GSIV: usize = 56;
+const NON_SECURE_EL1_TIMER_FLAGS: usize = 60;
+const VIRTUAL_TIMER_GSIV: usize = 64;
+const VIRTUAL_TIMER_FLAGS: usize = 68;
+const NON_SECURE_EL2_TIMER_GSIV: usize = 72;
+const NON_SECURE_EL2_TIMER_FLAGS: usize = 76;
+const CNT_READ_BASE: usize = 80;
+
+/// Builder for the GTDT. Every ARM generic timer interrupt defaults to
+/// level-triggered/active-high (flags = `0`) unless overridden.
+pub struct Gtdt {
+    sdt: Sdt,
+}
+
+impl Gtdt {
+    pub fn new(oem_id: [u8; 6], oem_table: [u8; 8], oem_revision: u32) -> Self {
+        let mut sdt = Sdt::new(
+            *b"GTDT",
+            GTDT_LENGTH,
+            GTDT_REVISION,
+            oem_id,
+            oem_table,
+            oem_revision,
+        );
+        sdt.write_u64(CNT_CONTROL_BASE, 0xffff_ffff_ffff_ffff); // not provided
+        sdt.write_u64(CNT_READ_BASE, 0xffff_ffff_ffff_ffff); // not provided
+        Gtdt { sdt }
+    }
+
+    pub fn set_secure_el1_timer(&mut self, gsiv: u32, flags: u32) -> &mut Self {
+        self.sdt.write_u32(SECURE_EL1_TIMER_GSIV, gsiv);
+        self.sdt.write_u32(SECURE_EL1_TIMER_FLAGS, flags);
+        self
+    }
+
+    pub fn set_non_secure_el1_timer(&mut self, gsiv: u32, flags: u32) -> &mut Self {
+        self.sdt.write_u32(NON_SECURE_EL1_TIMER_GSIV, gsiv);
+        self.sdt.write_u32(NON_SECURE_EL1_TIMER_FLAGS, flags);
+        self
+    }
+
+    pub fn set_virtual_timer(&mut self, gsiv: u32, flags: u32) -> &mut Self {
+        self.sdt.write_u32(VIRTUAL_TIMER_GSIV, gsiv);
+        self.sdt.write_u32(VIRTUAL_TIMER_FLAGS, flags);
+        self
+    }
+
+    pub fn set_non_secure_el2_timer(&mut self, gsiv: u32, flags: u32) -> &mut Self {
+        self.sdt.write_u32(NON_SECURE_EL2_TIMER_GSIV, gsiv);
+        self.sdt.write_u32(NON_SECURE_EL2_TIMER_FLAGS, flags);
+        self
+    }
+
+    pub fn set_cnt_control_base(&mut self, address: u64) -> &mut Self {
+        self.sdt.write_u64(CNT_CONTROL_BASE, address);
+        self
+    }
+
+    pub fn set_cnt_read_base(&mut self, address: u64) -> &mut Self {
+        self.sdt.write_u64(CNT_READ_BASE, address);
+        self
+    }
+}
+
+impl Aml for Gtdt {
+    fn to_aml_bytes(&self, sink: &mut dyn AmlSink) {
+        self.sdt.to_aml_bytes(sink);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Gtdt;
+
+    #[test]
+    fn test_gtdt_checksum_and_length() {
+        let mut gtdt = Gtdt::new(*b"CLOUDH", *b"TESTTEST", 1);
+        gtdt.set_non_secure_el1_timer(30, 4);
+        gtdt.set_virtual_timer(27, 4);
+
+        assert_eq!(gtdt.sdt.len(), 96);
+        let bytes = gtdt.sdt.as_slice();
+        assert_eq!(bytes.len(), 96);
+
+        let sum = bytes.iter().fold(0u8, |acc, byte| acc.wrapping_add(*byte));
+        assert_eq!(sum, 0);
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright © 2019 Intel Corporation
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+//! `vm-memory` integration for placing a finished [`Sdt`](crate::sdt::Sdt)
+//! into guest RAM, gated behind the `vm-memory` feature so the base crate
+//! stays dependency-free.
+//!
+//! VMMs build every table first, then lay them out in guest memory and only
+//! then learn the addresses needed for cross-table pointers (RSDT/XSDT
+//! entries, FADT -> DSDT, ...). [`FieldPatch`] lets a caller record where such
+//! a pointer field lives so it can be back-patched, and [`recompute_checksum`]
+//! re-folds the table's checksum afterwards.
+
+use crate::sdt::Sdt;
+use vm_memory::{Bytes, GuestAddress, GuestMemory, GuestMemoryError};
+
+/// The guest-address range a table occupies after [`write_table`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GuestRange {
+    pub address: GuestAddress,
+    pub length: usize,
+}
+
+/// Writes `sdt`'s bytes into `mem` at `address`.
+pub fn write_table<M: GuestMemory>(
+    mem: &M,
+    address: GuestAddress,
+    sdt: &Sdt,
+) -> Result<GuestRange, GuestMemoryError> {
+    mem.write_slice(sdt.as_slice(), address)?;
+    Ok(GuestRange {
+        address,
+        length: sdt.len(),
+    })
+}
+
+/// The guest address of a field inside a table already written to memory,
+/// recorded so it can be back-patched once every table's final address is
+/// known (e.g. a 64-bit XSDT entry slot pointing at a DSDT).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FieldPatch {
+    table_address: GuestAddress,
+    field_address: GuestAddress,
+}
+
+impl FieldPatch {
+    pub fn new(table_address: GuestAddress, field_offset: usize) -> Self {
+        FieldPatch {
+            table_address,
+            field_address: GuestAddress(table_address.0 + field_offset as u64),
+        }
+    }
+
+    /// Writes `value` at the recorded field address and recomputes the
+    /// containing table's checksum.
+    pub fn patch_u64<M: GuestMemory>(&self, mem: &M, value: u64) -> Result<(), GuestMemoryError> {
+        mem.write_slice(&value.to_le_bytes(), self.field_address)?;
+        recompute_checksum(mem, self.table_address)
+    }
+}
+
+/// Re-folds the ACPI checksum of the table at `table_address` directly in
+/// guest memory, for use after a caller has patched one of its fields in
+/// place.
+///
+/// ACPI tables are little-endian regardless of the host's native byte
+/// order, so every field is read/written via explicit `to_le_bytes`/
+/// `from_le_bytes` rather than `read_obj`/`write_obj`, which move values in
+/// host byte order.
+pub fn recompute_checksum<M: GuestMemory>(
+    mem: &M,
+    table_address: GuestAddress,
+) -> Result<(), GuestMemoryError> {
+    let mut length_bytes = [0u8; 4];
+    mem.read_slice(&mut length_bytes, GuestAddress(table_address.0 + 4))?;
+    let length = u32::from_le_bytes(length_bytes);
+
+    mem.write_slice(&[0u8], GuestAddress(table_address.0 + 9))?;
+    let mut sum = 0u8;
+    for offset in 0..length as u64 {
+        let mut byte = [0u8; 1];
+        mem.read_slice(&mut byte, GuestAddress(table_address.0 + offset))?;
+        sum = sum.wrapping_add(byte[0]);
+    }
+    mem.write_slice(
+        &[0u8.wrapping_sub(sum)],
+        GuestAddress(table_address.0 + 9),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdt::Sdt;
+    use alloc::vec;
+    use vm_memory::GuestMemoryMmap;
+
+    #[test]
+    fn test_write_table_and_patch_u64_keeps_checksum_valid() {
+        let mut sdt = Sdt::new(*b"TEST", 44, 1, *b"CLOUDH", *b"TESTTEST", 1);
+        sdt.write_u64(36, 0); // placeholder pointer field, back-patched below
+
+        let mem = GuestMemoryMmap::<()>::from_ranges(&[(GuestAddress(0), 0x1000)]).unwrap();
+        let address = GuestAddress(0x100);
+
+        let range = write_table(&mem, address, &sdt).unwrap();
+        assert_eq!(range.address, address);
+        assert_eq!(range.length, sdt.len());
+
+        let patch = FieldPatch::new(address, 36);
+        patch.patch_u64(&mem, 0xdead_beef_1234_5678).unwrap();
+
+        let mut bytes = vec![0u8; range.length];
+        mem.read_slice(&mut bytes, address).unwrap();
+
+        let sum = bytes.iter().fold(0u8, |acc, byte| acc.wrapping_add(*byte));
+        assert_eq!(sum, 0);
+
+        let patched = u64::from_le_bytes(bytes[36..44].try_into().unwrap());
+        assert_eq!(patched, 0xdead_beef_1234_5678);
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright © 2019 Intel Corporation
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+pub mod sdt;
+
+mod fadt;
+mod gtdt;
+mod madt;
+mod numa;
+
+#[cfg(feature = "bytes")]
+mod bytes_sink;
+
+#[cfg(feature = "vm-memory")]
+mod guest_memory;
+
+pub use fadt::Fadt;
+pub use gtdt::Gtdt;
+pub use madt::{Madt, MadtEntry};
+pub use numa::{Slit, Srat, SratAffinity};
+pub use sdt::{AccessSize, AddressSpaceId, GenericAddress, Sdt};
+
+#[cfg(feature = "bytes")]
+pub use bytes_sink::BufMutSink;
+
+#[cfg(feature = "vm-memory")]
+pub use guest_memory::{recompute_checksum, write_table, FieldPatch, GuestRange};
+
+pub(crate) const CREATOR_ID: [u8; 4] = *b"ACPI";
+pub(crate) const CREATOR_REVISION: [u8; 4] = 1u32.to_le_bytes();
+
+/// A sink that ACPI/AML table bytes are streamed into.
+pub trait AmlSink {
+    fn byte(&mut self, byte: u8);
+
+    fn vec(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.byte(byte);
+        }
+    }
+}
+
+impl AmlSink for Vec<u8> {
+    fn byte(&mut self, byte: u8) {
+        self.push(byte);
+    }
+
+    fn vec(&mut self, data: &[u8]) {
+        self.extend_from_slice(data);
+    }
+}
+
+/// Implemented by anything that can serialize itself into ACPI/AML bytes.
+pub trait Aml {
+    fn to_aml_bytes(&self, sink: &mut dyn AmlSink);
+}
@@ -0,0 +1,232 @@
+// Copyright © 2019 Intel Corporation
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+//! Typed builder for the Multiple APIC Description Table (MADT), covering
+//! the interrupt-controller structures VMMs actually emit: Local APIC and
+//! I/O APIC for x86, and GIC CPU/Distributor/Redistributor/ITS for aarch64.
+
+extern crate alloc;
+
+use crate::sdt::Sdt;
+use crate::{Aml, AmlSink};
+use alloc::vec::Vec;
+
+const MADT_REVISION: u8 = 5;
+
+/// One entry of the MADT's variable-length interrupt-controller-structure
+/// list (ACPI spec, "Multiple APIC Description Table").
+pub enum MadtEntry {
+    LocalApic {
+        processor_id: u8,
+        apic_id: u8,
+        flags: u32,
+    },
+    IoApic {
+        ioapic_id: u8,
+        address: u32,
+        gsi_base: u32,
+    },
+    GicCpu {
+        acpi_processor_uid: u32,
+        flags: u32,
+        parked_address: u64,
+        base_address: u64,
+        gicv_base_address: u64,
+        gich_base_address: u64,
+        vgic_interrupt: u32,
+        gicr_base_address: u64,
+        mpidr: u64,
+    },
+    GicDistributor {
+        gic_id: u32,
+        base_address: u64,
+        gic_version: u8,
+    },
+    GicRedistributor {
+        base_address: u64,
+        length: u32,
+    },
+    GicIts {
+        translation_id: u32,
+        base_address: u64,
+    },
+}
+
+impl MadtEntry {
+    // Each entry is serialized as `type: u8, length: u8` followed by its
+    // body, per the ACPI "Interrupt Controller Structure" layout.
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut body = Vec::new();
+        let entry_type: u8 = match self {
+            MadtEntry::LocalApic {
+                processor_id,
+                apic_id,
+                flags,
+            } => {
+                body.push(*processor_id);
+                body.push(*apic_id);
+                body.extend_from_slice(&flags.to_le_bytes());
+                0
+            }
+            MadtEntry::IoApic {
+                ioapic_id,
+                address,
+                gsi_base,
+            } => {
+                body.push(*ioapic_id);
+                body.push(0); // reserved
+                body.extend_from_slice(&address.to_le_bytes());
+                body.extend_from_slice(&gsi_base.to_le_bytes());
+                1
+            }
+            MadtEntry::GicCpu {
+                acpi_processor_uid,
+                flags,
+                parked_address,
+                base_address,
+                gicv_base_address,
+                gich_base_address,
+                vgic_interrupt,
+                gicr_base_address,
+                mpidr,
+            } => {
+                body.extend_from_slice(&0u16.to_le_bytes()); // reserved
+                body.extend_from_slice(&0u32.to_le_bytes()); // CPU interface number, unused on GICv3+
+                body.extend_from_slice(&acpi_processor_uid.to_le_bytes());
+                body.extend_from_slice(&flags.to_le_bytes());
+                body.extend_from_slice(&0u32.to_le_bytes()); // parking protocol version
+                body.extend_from_slice(&0u32.to_le_bytes()); // performance interrupt
+                body.extend_from_slice(&parked_address.to_le_bytes());
+                body.extend_from_slice(&base_address.to_le_bytes());
+                body.extend_from_slice(&gicv_base_address.to_le_bytes());
+                body.extend_from_slice(&gich_base_address.to_le_bytes());
+                body.extend_from_slice(&vgic_interrupt.to_le_bytes());
+                body.extend_from_slice(&gicr_base_address.to_le_bytes());
+                body.extend_from_slice(&mpidr.to_le_bytes());
+                body.push(0); // power efficiency class
+                body.push(0); // reserved
+                body.extend_from_slice(&0u16.to_le_bytes()); // SPE overflow interrupt
+                0x0b
+            }
+            MadtEntry::GicDistributor {
+                gic_id,
+                base_address,
+                gic_version,
+            } => {
+                body.extend_from_slice(&0u16.to_le_bytes()); // reserved
+                body.extend_from_slice(&gic_id.to_le_bytes());
+                body.extend_from_slice(&base_address.to_le_bytes());
+                body.extend_from_slice(&0u32.to_le_bytes()); // global system interrupt base
+                body.push(*gic_version);
+                body.extend_from_slice(&[0u8; 3]); // reserved
+                0x0c
+            }
+            MadtEntry::GicRedistributor {
+                base_address,
+                length,
+            } => {
+                body.extend_from_slice(&0u16.to_le_bytes()); // reserved
+                body.extend_from_slice(&base_address.to_le_bytes());
+                body.extend_from_slice(&length.to_le_bytes());
+                0x0e
+            }
+            MadtEntry::GicIts {
+                translation_id,
+                base_address,
+            } => {
+                body.extend_from_slice(&0u16.to_le_bytes()); // reserved
+                body.extend_from_slice(&translation_id.to_le_bytes());
+                body.extend_from_slice(&base_address.to_le_bytes());
+                body.extend_from_slice(&0u32.to_le_bytes()); // reserved
+                0x0f
+            }
+        };
+
+        let mut entry = Vec::with_capacity(2 + body.len());
+        entry.push(entry_type);
+        entry.push((2 + body.len()) as u8);
+        entry.extend_from_slice(&body);
+        entry
+    }
+}
+
+/// Builder for the MADT. Entries are appended through [`Sdt::append_slice`],
+/// keeping the header `length` and checksum consistent as entries are added.
+pub struct Madt {
+    sdt: Sdt,
+}
+
+impl Madt {
+    pub fn new(
+        oem_id: [u8; 6],
+        oem_table: [u8; 8],
+        oem_revision: u32,
+        local_apic_address: u32,
+        flags: u32,
+    ) -> Self {
+        let mut sdt = Sdt::new(*b"APIC", 36, MADT_REVISION, oem_id, oem_table, oem_revision);
+        sdt.append(local_apic_address);
+        sdt.append(flags);
+        Madt { sdt }
+    }
+
+    pub fn add_entry(&mut self, entry: MadtEntry) -> &mut Self {
+        self.sdt.append_slice(&entry.to_bytes());
+        self
+    }
+}
+
+impl Aml for Madt {
+    fn to_aml_bytes(&self, sink: &mut dyn AmlSink) {
+        self.sdt.to_aml_bytes(sink);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Madt, MadtEntry};
+
+    #[test]
+    fn test_gic_cpu_entry_is_80_bytes() {
+        let entry = MadtEntry::GicCpu {
+            acpi_processor_uid: 0,
+            flags: 1,
+            parked_address: 0,
+            base_address: 0,
+            gicv_base_address: 0,
+            gich_base_address: 0,
+            vgic_interrupt: 0,
+            gicr_base_address: 0,
+            mpidr: 0,
+        };
+        assert_eq!(entry.to_bytes().len(), 80);
+        assert_eq!(entry.to_bytes()[0], 0x0b); // GICC entry type
+        assert_eq!(entry.to_bytes()[1], 80); // entry length byte
+    }
+
+    #[test]
+    fn test_madt_checksum_and_length() {
+        let mut madt = Madt::new(*b"CLOUDH", *b"TESTTEST", 1, 0xfee0_0000, 1);
+        madt.add_entry(MadtEntry::LocalApic {
+            processor_id: 0,
+            apic_id: 0,
+            flags: 1,
+        });
+        madt.add_entry(MadtEntry::IoApic {
+            ioapic_id: 0,
+            address: 0xfec0_0000,
+            gsi_base: 0,
+        });
+
+        let bytes = madt.sdt.as_slice();
+        // 44-byte header (36-byte SDT header + local APIC address + flags)
+        // plus an 8-byte Local APIC entry and a 12-byte I/O APIC entry.
+        assert_eq!(bytes.len(), 44 + 8 + 12);
+        assert_eq!(madt.sdt.len(), bytes.len());
+
+        let sum = bytes.iter().fold(0u8, |acc, byte| acc.wrapping_add(*byte));
+        assert_eq!(sum, 0);
+    }
+}
@@ -0,0 +1,243 @@
+// Copyright © 2019 Intel Corporation
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+//! Typed builders for the NUMA topology tables: the Static Resource
+//! Affinity Table (SRAT) and the System Locality Distance Information
+//! Table (SLIT).
+
+extern crate alloc;
+
+use crate::sdt::Sdt;
+use crate::{Aml, AmlSink};
+use alloc::vec::Vec;
+
+const SRAT_REVISION: u8 = 3;
+const SLIT_REVISION: u8 = 1;
+
+/// One entry of the SRAT's variable-length affinity-structure list (ACPI
+/// spec, "System/Static Resource Affinity Table").
+pub enum SratAffinity {
+    /// x86 Processor Local APIC/SAPIC Affinity Structure.
+    LocalApic { proximity_domain: u32, apic_id: u8 },
+    /// Memory Affinity Structure.
+    Memory {
+        proximity_domain: u32,
+        base_address: u64,
+        length: u64,
+        hotpluggable: bool,
+        non_volatile: bool,
+    },
+    /// Processor Local x2APIC Affinity Structure.
+    LocalX2Apic { proximity_domain: u32, x2apic_id: u32 },
+    /// aarch64 GICC Affinity Structure.
+    Gicc { proximity_domain: u32, acpi_processor_uid: u32 },
+}
+
+impl SratAffinity {
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut body = Vec::new();
+        let entry_type: u8 = match self {
+            SratAffinity::LocalApic {
+                proximity_domain,
+                apic_id,
+            } => {
+                body.push(proximity_domain.to_le_bytes()[0]);
+                body.push(*apic_id);
+                body.extend_from_slice(&1u32.to_le_bytes()); // flags: enabled
+                body.push(0); // local SAPIC EID
+                body.extend_from_slice(&proximity_domain.to_le_bytes()[1..4]);
+                body.extend_from_slice(&0u32.to_le_bytes()); // clock domain
+                0
+            }
+            SratAffinity::Memory {
+                proximity_domain,
+                base_address,
+                length,
+                hotpluggable,
+                non_volatile,
+            } => {
+                body.extend_from_slice(&proximity_domain.to_le_bytes());
+                body.extend_from_slice(&0u16.to_le_bytes()); // reserved
+                body.extend_from_slice(&base_address.to_le_bytes());
+                body.extend_from_slice(&length.to_le_bytes());
+                body.extend_from_slice(&0u32.to_le_bytes()); // reserved
+                let mut flags = 1u32; // enabled
+                if *hotpluggable {
+                    flags |= 1 << 1;
+                }
+                if *non_volatile {
+                    flags |= 1 << 2;
+                }
+                body.extend_from_slice(&flags.to_le_bytes());
+                body.extend_from_slice(&0u64.to_le_bytes()); // reserved
+                1
+            }
+            SratAffinity::LocalX2Apic {
+                proximity_domain,
+                x2apic_id,
+            } => {
+                body.extend_from_slice(&0u16.to_le_bytes()); // reserved
+                body.extend_from_slice(&proximity_domain.to_le_bytes());
+                body.extend_from_slice(&x2apic_id.to_le_bytes());
+                body.extend_from_slice(&1u32.to_le_bytes()); // flags: enabled
+                body.extend_from_slice(&0u32.to_le_bytes()); // clock domain
+                body.extend_from_slice(&0u32.to_le_bytes()); // reserved
+                2
+            }
+            SratAffinity::Gicc {
+                proximity_domain,
+                acpi_processor_uid,
+            } => {
+                body.extend_from_slice(&proximity_domain.to_le_bytes());
+                body.extend_from_slice(&acpi_processor_uid.to_le_bytes());
+                body.extend_from_slice(&1u32.to_le_bytes()); // flags: enabled
+                body.extend_from_slice(&0u32.to_le_bytes()); // clock domain
+                3
+            }
+        };
+
+        let mut entry = Vec::with_capacity(2 + body.len());
+        entry.push(entry_type);
+        entry.push((2 + body.len()) as u8);
+        entry.extend_from_slice(&body);
+        entry
+    }
+}
+
+/// Builder for the SRAT. Affinity structures are appended through
+/// [`Sdt::append_slice`].
+pub struct Srat {
+    sdt: Sdt,
+}
+
+impl Srat {
+    pub fn new(oem_id: [u8; 6], oem_table: [u8; 8], oem_revision: u32) -> Self {
+        let mut sdt = Sdt::new(*b"SRAT", 36, SRAT_REVISION, oem_id, oem_table, oem_revision);
+        sdt.append(1u32); // table revision, fixed at 1 by the ACPI spec
+        sdt.append(0u64); // reserved
+        Srat { sdt }
+    }
+
+    pub fn add_affinity(&mut self, affinity: SratAffinity) -> &mut Self {
+        self.sdt.append_slice(&affinity.to_bytes());
+        self
+    }
+}
+
+impl Aml for Srat {
+    fn to_aml_bytes(&self, sink: &mut dyn AmlSink) {
+        self.sdt.to_aml_bytes(sink);
+    }
+}
+
+/// Builder for the SLIT. Relative distances are a full `localities x
+/// localities` matrix, row-major, so [`Slit::new`] takes the locality count
+/// up front and [`Slit::set_distance`] patches individual entries.
+pub struct Slit {
+    sdt: Sdt,
+    localities: u64,
+}
+
+impl Slit {
+    pub fn new(oem_id: [u8; 6], oem_table: [u8; 8], oem_revision: u32, localities: u64) -> Self {
+        let mut sdt = Sdt::new(*b"SLIT", 36, SLIT_REVISION, oem_id, oem_table, oem_revision);
+        sdt.append(localities);
+        // Default every locality to equidistant-from-itself / unreachable
+        // from everywhere else, matching the ACPI-defined special values.
+        for from in 0..localities {
+            for to in 0..localities {
+                let distance = if from == to { 10 } else { 0xff };
+                sdt.append(distance as u8);
+            }
+        }
+        Slit { sdt, localities }
+    }
+
+    pub fn set_distance(&mut self, from: u64, to: u64, distance: u8) -> &mut Self {
+        assert!(from < self.localities && to < self.localities);
+        let offset = 44 + (from * self.localities + to) as usize;
+        self.sdt.write_u8(offset, distance);
+        self
+    }
+}
+
+impl Aml for Slit {
+    fn to_aml_bytes(&self, sink: &mut dyn AmlSink) {
+        self.sdt.to_aml_bytes(sink);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Slit, Srat, SratAffinity};
+
+    #[test]
+    fn test_srat_affinity_entry_lengths() {
+        let memory = SratAffinity::Memory {
+            proximity_domain: 0,
+            base_address: 0,
+            length: 0,
+            hotpluggable: false,
+            non_volatile: false,
+        };
+        assert_eq!(memory.to_bytes().len(), 40);
+
+        let local_apic = SratAffinity::LocalApic {
+            proximity_domain: 0,
+            apic_id: 0,
+        };
+        assert_eq!(local_apic.to_bytes().len(), 16);
+
+        let local_x2apic = SratAffinity::LocalX2Apic {
+            proximity_domain: 0,
+            x2apic_id: 0,
+        };
+        assert_eq!(local_x2apic.to_bytes().len(), 24);
+
+        let gicc = SratAffinity::Gicc {
+            proximity_domain: 0,
+            acpi_processor_uid: 0,
+        };
+        assert_eq!(gicc.to_bytes().len(), 18);
+    }
+
+    #[test]
+    fn test_srat_checksum_and_length() {
+        let mut srat = Srat::new(*b"CLOUDH", *b"TESTTEST", 1);
+        srat.add_affinity(SratAffinity::Memory {
+            proximity_domain: 0,
+            base_address: 0,
+            length: 0x1000_0000,
+            hotpluggable: false,
+            non_volatile: false,
+        });
+
+        let bytes = srat.sdt.as_slice();
+        // 48-byte header (36-byte SDT header + table revision + reserved)
+        // plus a 40-byte Memory Affinity Structure.
+        assert_eq!(bytes.len(), 48 + 40);
+        assert_eq!(srat.sdt.len(), bytes.len());
+
+        let sum = bytes.iter().fold(0u8, |acc, byte| acc.wrapping_add(*byte));
+        assert_eq!(sum, 0);
+    }
+
+    #[test]
+    fn test_slit_checksum_and_distance_matrix() {
+        let mut slit = Slit::new(*b"CLOUDH", *b"TESTTEST", 1, 2);
+        slit.set_distance(0, 1, 20);
+        slit.set_distance(1, 0, 20);
+
+        let bytes = slit.sdt.as_slice();
+        // 44-byte header (36-byte SDT header + locality count) plus the
+        // 2x2 distance matrix.
+        assert_eq!(bytes.len(), 44 + 4);
+        assert_eq!(slit.sdt.len(), bytes.len());
+        assert_eq!(&bytes[44..48], &[10, 20, 20, 10]);
+
+        let sum = bytes.iter().fold(0u8, |acc, byte| acc.wrapping_add(*byte));
+        assert_eq!(sum, 0);
+    }
+}
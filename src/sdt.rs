@@ -8,7 +8,82 @@ extern crate alloc;
 use crate::{Aml, AmlSink};
 use alloc::vec::Vec;
 
-#[repr(packed)]
+/// The `Address Space ID` field of a Generic Address Structure (ACPI spec,
+/// "Generic Address Structure"), identifying which address space
+/// `GenericAddress::address` is expressed in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressSpaceId {
+    SystemMemory,
+    SystemIo,
+    PciConfigSpace,
+    EmbeddedController,
+    SmBus,
+    SystemCmos,
+    PciBarTarget,
+    Ipmi,
+    GeneralPurposeIo,
+    GenericSerialBus,
+    PlatformCommunicationsChannel,
+    FunctionalFixedHardware,
+}
+
+impl From<AddressSpaceId> for u8 {
+    fn from(id: AddressSpaceId) -> u8 {
+        match id {
+            AddressSpaceId::SystemMemory => 0,
+            AddressSpaceId::SystemIo => 1,
+            AddressSpaceId::PciConfigSpace => 2,
+            AddressSpaceId::EmbeddedController => 3,
+            AddressSpaceId::SmBus => 4,
+            AddressSpaceId::SystemCmos => 5,
+            AddressSpaceId::PciBarTarget => 6,
+            AddressSpaceId::Ipmi => 7,
+            AddressSpaceId::GeneralPurposeIo => 8,
+            AddressSpaceId::GenericSerialBus => 9,
+            AddressSpaceId::PlatformCommunicationsChannel => 0x0a,
+            AddressSpaceId::FunctionalFixedHardware => 0x7f,
+        }
+    }
+}
+
+/// The `Access Size` field of a Generic Address Structure. This is distinct
+/// from `register_bit_width`: it describes the size of each individual
+/// access used to reach the register, e.g. a 32-bit register that must be
+/// accessed one byte at a time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessSize {
+    Undefined,
+    Byte,
+    Word,
+    Dword,
+    Qword,
+}
+
+impl From<AccessSize> for u8 {
+    fn from(size: AccessSize) -> u8 {
+        match size {
+            AccessSize::Undefined => 0,
+            AccessSize::Byte => 1,
+            AccessSize::Word => 2,
+            AccessSize::Dword => 3,
+            AccessSize::Qword => 4,
+        }
+    }
+}
+
+impl From<usize> for AccessSize {
+    fn from(size_in_bytes: usize) -> Self {
+        match size_in_bytes {
+            1 => AccessSize::Byte,
+            2 => AccessSize::Word,
+            4 => AccessSize::Dword,
+            8 => AccessSize::Qword,
+            _ => AccessSize::Undefined,
+        }
+    }
+}
+
+#[repr(C, packed)]
 #[derive(Clone, Copy)]
 pub struct GenericAddress {
     pub address_space_id: u8,
@@ -19,28 +94,122 @@ pub struct GenericAddress {
 }
 
 impl GenericAddress {
+    pub fn builder() -> GenericAddressBuilder {
+        GenericAddressBuilder::default()
+    }
+
     pub fn io_port_address<T>(address: u16) -> Self {
-        GenericAddress {
-            address_space_id: 1,
-            register_bit_width: 8 * core::mem::size_of::<T>() as u8,
-            register_bit_offset: 0,
-            access_size: core::mem::size_of::<T>() as u8,
-            address: u64::from(address),
-        }
+        GenericAddress::builder()
+            .address_space(AddressSpaceId::SystemIo)
+            .register_bit_width(8 * core::mem::size_of::<T>() as u8)
+            .access_size(AccessSize::from(core::mem::size_of::<T>()))
+            .address(u64::from(address))
+            .build()
     }
+
     pub fn mmio_address<T>(address: u64) -> Self {
+        GenericAddress::builder()
+            .address_space(AddressSpaceId::SystemMemory)
+            .register_bit_width(8 * core::mem::size_of::<T>() as u8)
+            .access_size(AccessSize::from(core::mem::size_of::<T>()))
+            .address(address)
+            .build()
+    }
+
+    /// Serialize to the 12-byte on-the-wire Generic Address Structure.
+    pub fn to_bytes(self) -> [u8; 12] {
+        let mut bytes = [0u8; 12];
+        bytes[0] = self.address_space_id;
+        bytes[1] = self.register_bit_width;
+        bytes[2] = self.register_bit_offset;
+        bytes[3] = self.access_size;
+        bytes[4..12].copy_from_slice(&self.address.to_le_bytes());
+        bytes
+    }
+}
+
+/// Builder for [`GenericAddress`], allowing `register_bit_width`,
+/// `register_bit_offset`, and `access_size` to be set independently of each
+/// other, e.g. to describe a 32-bit register accessed one byte at a time or
+/// a PCI configuration space address.
+#[derive(Clone, Copy, Default)]
+pub struct GenericAddressBuilder {
+    address_space_id: u8,
+    register_bit_width: u8,
+    register_bit_offset: u8,
+    access_size: u8,
+    address: u64,
+}
+
+impl GenericAddressBuilder {
+    pub fn address_space(mut self, address_space_id: AddressSpaceId) -> Self {
+        self.address_space_id = address_space_id.into();
+        self
+    }
+
+    pub fn register_bit_width(mut self, register_bit_width: u8) -> Self {
+        self.register_bit_width = register_bit_width;
+        self
+    }
+
+    pub fn register_bit_offset(mut self, register_bit_offset: u8) -> Self {
+        self.register_bit_offset = register_bit_offset;
+        self
+    }
+
+    pub fn access_size(mut self, access_size: AccessSize) -> Self {
+        self.access_size = access_size.into();
+        self
+    }
+
+    pub fn address(mut self, address: u64) -> Self {
+        self.address = address;
+        self
+    }
+
+    pub fn build(self) -> GenericAddress {
         GenericAddress {
-            address_space_id: 0,
-            register_bit_width: 8 * core::mem::size_of::<T>() as u8,
-            register_bit_offset: 0,
-            access_size: core::mem::size_of::<T>() as u8,
-            address,
+            address_space_id: self.address_space_id,
+            register_bit_width: self.register_bit_width,
+            register_bit_offset: self.register_bit_offset,
+            access_size: self.access_size,
+            address: self.address,
         }
     }
 }
 
+/// Types that can be serialized into little-endian bytes for writing into an
+/// `Sdt`. ACPI tables are defined as little-endian regardless of the host's
+/// native byte order, so `Sdt::write` is restricted to types that know how to
+/// produce that representation rather than relying on a raw, host-endian
+/// pointer store.
+pub trait LittleEndian: Copy {
+    type Bytes: AsRef<[u8]>;
+    fn to_le_bytes(self) -> Self::Bytes;
+}
+
+macro_rules! impl_little_endian {
+    ($($t:ty),*) => {
+        $(
+            impl LittleEndian for $t {
+                type Bytes = [u8; core::mem::size_of::<$t>()];
+                fn to_le_bytes(self) -> Self::Bytes {
+                    <$t>::to_le_bytes(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_little_endian!(u8, u16, u32, u64);
+
 pub struct Sdt {
     data: Vec<u8>,
+    // Running wrapping sum of every byte in `data` except the checksum byte
+    // itself (offset 9). The checksum is always `0u8.wrapping_sub(checksum_sum)`,
+    // which keeps `finalize()` an O(1) operation instead of re-folding the
+    // whole buffer on every mutation.
+    checksum_sum: u8,
 }
 
 impl AmlSink for Sdt {
@@ -73,23 +242,51 @@ impl Sdt {
         assert_eq!(data.len(), 36);
 
         data.resize(length as usize, 0);
-        let mut sdt = Sdt { data };
 
-        sdt.update_checksum();
+        let checksum_sum = Self::sum_excluding_checksum(&data, 0, data.len());
+        let mut sdt = Sdt { data, checksum_sum };
+
+        sdt.finalize();
         sdt
     }
 
+    // Wrapping sum of `data[offset..offset + len]`, skipping the checksum
+    // byte at offset 9 (its contribution is always folded into `checksum_sum`
+    // as zero, since it is reconstituted from the sum rather than summed).
+    fn sum_excluding_checksum(data: &[u8], offset: usize, len: usize) -> u8 {
+        data[offset..offset + len]
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| offset + i != 9)
+            .fold(0u8, |acc, (_, byte)| acc.wrapping_add(*byte))
+    }
+
+    fn adjust_checksum(&mut self, old_sum: u8, new_sum: u8) {
+        self.checksum_sum = self.checksum_sum.wrapping_sub(old_sum).wrapping_add(new_sum);
+        self.finalize();
+    }
+
+    /// Recompute the checksum byte from the running sum in O(1) and return it.
+    pub fn finalize(&mut self) -> u8 {
+        let checksum = 0u8.wrapping_sub(self.checksum_sum);
+        self.data[9] = checksum;
+        checksum
+    }
+
+    /// The table's current checksum byte.
+    pub fn checksum(&self) -> u8 {
+        self.data[9]
+    }
+
     pub fn update_checksum(&mut self) {
-        self.data[9] = 0;
-        let checksum = super::generate_checksum(self.data.as_slice());
-        self.data[9] = checksum
+        self.finalize();
     }
 
     pub fn as_slice(&self) -> &[u8] {
         self.data.as_slice()
     }
 
-    pub fn append<T>(&mut self, value: T) {
+    pub fn append<T: LittleEndian>(&mut self, value: T) {
         let orig_length = self.data.len();
         let new_length = orig_length + core::mem::size_of::<T>();
         self.data.resize(new_length, 0);
@@ -101,18 +298,31 @@ impl Sdt {
         let orig_length = self.data.len();
         let new_length = orig_length + data.len();
         self.write_u32(4, new_length as u32);
+        let appended_sum = data.iter().fold(0u8, |acc, byte| acc.wrapping_add(*byte));
         self.data.extend_from_slice(data);
-        self.update_checksum();
+        self.adjust_checksum(0, appended_sum);
     }
 
-    /// Write a value at the given offset
-    pub fn write<T>(&mut self, offset: usize, value: T) {
-        assert!((offset + (core::mem::size_of::<T>() - 1)) < self.data.len());
-        // SAFETY: The assertion above makes sure we don't do out of bounds write.
-        unsafe {
-            *(((self.data.as_mut_ptr() as usize) + offset) as *mut T) = value;
-        }
-        self.update_checksum();
+    /// Write a value at the given offset, serialized as little-endian bytes.
+    pub fn write<T: LittleEndian>(&mut self, offset: usize, value: T) {
+        let bytes = value.to_le_bytes();
+        let bytes = bytes.as_ref();
+        let size = bytes.len();
+        assert!((offset + (size - 1)) < self.data.len());
+        let old_sum = Self::sum_excluding_checksum(&self.data, offset, size);
+        self.data[offset..offset + size].copy_from_slice(bytes);
+        let new_sum = Self::sum_excluding_checksum(&self.data, offset, size);
+        self.adjust_checksum(old_sum, new_sum);
+    }
+
+    /// Write a run of bytes at the given offset, e.g. a serialized
+    /// `GenericAddress`, without resizing the table.
+    pub fn write_bytes(&mut self, offset: usize, data: &[u8]) {
+        assert!(offset + data.len() <= self.data.len());
+        let old_sum = Self::sum_excluding_checksum(&self.data, offset, data.len());
+        self.data[offset..offset + data.len()].copy_from_slice(data);
+        let new_sum = Self::sum_excluding_checksum(&self.data, offset, data.len());
+        self.adjust_checksum(old_sum, new_sum);
     }
 
     pub fn write_u8(&mut self, offset: usize, val: u8) {
@@ -148,7 +358,17 @@ impl Aml for Sdt {
 
 #[cfg(test)]
 mod tests {
-    use super::Sdt;
+    use super::{GenericAddress, Sdt};
+
+    #[test]
+    fn test_generic_address_access_size_encoding() {
+        // `access_size` is the ACPI-defined enumerant, not the raw byte
+        // count: a u32 register encodes as `AccessSize::Dword` (3), not 4.
+        assert_eq!(GenericAddress::io_port_address::<u16>(0).to_bytes()[3], 2);
+        assert_eq!(GenericAddress::io_port_address::<u32>(0).to_bytes()[3], 3);
+        assert_eq!(GenericAddress::mmio_address::<u16>(0).to_bytes()[3], 2);
+        assert_eq!(GenericAddress::mmio_address::<u32>(0).to_bytes()[3], 3);
+    }
 
     #[test]
     fn test_sdt() {